@@ -1,9 +1,16 @@
+use std::collections::HashMap;
 use std::env;
-use std::ffi::CString;
+use std::ffi::{CString, OsStr, OsString};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::RawFd;
+use nix::libc;
 use nix::unistd;
 use nix::sys::ptrace;
+use nix::sys::ptrace::Event;
+use nix::sys::signal;
 use nix::sys::uio;
 use nix::sys::wait;
+use nix::sys::wait::WaitStatus;
 use crate::syscalls;
 
 enum ProcessFileState {
@@ -53,97 +60,476 @@ pub fn get_child_buffer(pid: unistd::Pid, base: usize, len: usize) -> String {
     String::from_utf8_lossy(&rbuf).into_owned()
 }
 
-pub fn get_child_buffer_cstr(pid: unistd::Pid, base: usize) -> String {
-    let mut final_buf: Vec<u8> = Vec::with_capacity(255);
+/// Typical page size on the platforms this tracer targets; used only to keep
+/// a single read or write of tracee memory from crossing a page boundary.
+const PAGE_SIZE: usize = 4096;
 
-    // Current RemoteIoVec base address
-    let mut current_base = base;
+/// Read up to `len` bytes from `pid`'s memory at `base`.
+fn read_chunk(pid: unistd::Pid, base: usize, len: usize) -> nix::Result<Vec<u8>> {
+    let mut rbuf: Vec<u8> = vec![0; len];
+    let remote_iovec = uio::RemoteIoVec{ base: base, len: len };
+    let bytes_read = uio::process_vm_readv(
+        pid,
+        &[uio::IoVec::from_mut_slice(rbuf.as_mut_slice())],
+        &[remote_iovec],
+    )?;
+    rbuf.truncate(bytes_read);
+    Ok(rbuf)
+}
 
-    // Index of 0 byte in final_buf
-    let mut nul_idx: isize= -1;
+/// Read one page-boundary-safe chunk from `pid`'s memory at `base`, i.e. at
+/// most as many bytes as remain until the next page boundary. If the full
+/// request fails, fall back once to half the length, so one bad pointer
+/// doesn't have to panic the whole tracer.
+fn read_cstr_chunk(pid: unistd::Pid, base: usize) -> nix::Result<Vec<u8>> {
+    let to_boundary = PAGE_SIZE - (base % PAGE_SIZE);
+    match read_chunk(pid, base, to_boundary) {
+        Ok(chunk) => Ok(chunk),
+        Err(_) if to_boundary > 1 => read_chunk(pid, base, to_boundary / 2),
+        Err(err) => Err(err),
+    }
+}
+
+/// Read a NUL-terminated C string out of `pid`'s memory starting at `base`,
+/// via `read_cstr_chunk`, picking up where each chunk left off until the NUL.
+pub fn get_child_buffer_cstr(pid: unistd::Pid, base: usize) -> Result<String, String> {
+    let mut final_buf: Vec<u8> = Vec::new();
+    let mut current_base = base;
 
-    // Keep reading 255-byte chunks from the process VM until one contains a 0 byte
-    // (null-termination character)
     loop {
+        let chunk = read_cstr_chunk(pid, current_base).map_err(|err| {
+            format!(
+                "Unable to read C string from child process virtual memory at {:#x}: {}",
+                current_base, err,
+            )
+        })?;
 
-        // Read into a temporary buffer
-        let mut rbuf: Vec<u8> = vec![0; 255];
-        let remote_iovec = uio::RemoteIoVec{ base: current_base, len: 255 };
-        uio::process_vm_readv(
-            pid,
-            &[uio::IoVec::from_mut_slice(rbuf.as_mut_slice())],
-            &[remote_iovec],
-        )
-            .expect("Unable to read from child process virtual memory");
-
-        // Append temporary buffer to the final buffer and increase base address pointer
-        final_buf.append(&mut rbuf);
-        current_base += 255;
-
-        // If final_buf contains a 0 byte, store the index and break from the read loop
-        if final_buf.contains(&0) {
-            if let Some(idx) = final_buf.iter().position(|&x| x == 0) {
-                nul_idx = idx as isize;
-            }
-            break;
+        if let Some(nul_idx) = chunk.iter().position(|&b| b == 0) {
+            final_buf.extend_from_slice(&chunk[..nul_idx]);
+            return Ok(String::from_utf8_lossy(&final_buf).into_owned());
+        }
+
+        if chunk.is_empty() {
+            return Err(format!(
+                "Unable to read C string from child process virtual memory at {:#x}: no NUL terminator found",
+                base,
+            ));
         }
+
+        final_buf.extend_from_slice(&chunk);
+        current_base += chunk.len();
     }
-    if nul_idx > -1 {
-        String::from_utf8_lossy(&final_buf[0..(nul_idx as usize)]).into_owned()
-    } else {
-        String::from("")
+}
+
+/// Write up to `len` bytes of `buf` to `pid`'s memory at `base`.
+fn write_chunk(pid: unistd::Pid, base: usize, buf: &[u8], len: usize) -> nix::Result<usize> {
+    let remote_iovec = uio::RemoteIoVec{ base: base, len: len };
+    uio::process_vm_writev(
+        pid,
+        &[uio::IoVec::from_slice(&buf[..len])],
+        &[remote_iovec],
+    )
+}
+
+/// Write one page-boundary-safe chunk of `buf` to `pid`'s memory at `base`,
+/// i.e. at most as many bytes as remain until the next page boundary. If the
+/// full request fails, fall back once to half the length, same as
+/// `read_cstr_chunk`.
+fn write_cstr_chunk(pid: unistd::Pid, base: usize, buf: &[u8]) -> nix::Result<usize> {
+    let to_boundary = PAGE_SIZE - (base % PAGE_SIZE);
+    let len = buf.len().min(to_boundary);
+    match write_chunk(pid, base, buf, len) {
+        Ok(n) => Ok(n),
+        Err(_) if len > 1 => write_chunk(pid, base, buf, len / 2),
+        Err(err) => Err(err),
     }
 }
 
-pub fn exec_child(child_cmd: String, args: env::Args) {
-    ptrace::traceme().expect("CHILD: could not enable tracing by parent (PTRACE_TRACEME failed)");
+/// Write `buf` into `pid`'s memory starting at `base`, via `write_cstr_chunk`.
+pub fn put_child_buffer(pid: unistd::Pid, base: usize, buf: &[u8]) -> Result<(), String> {
+    let mut written = 0;
+    while written < buf.len() {
+        let current_base = base + written;
+        let remaining = &buf[written..];
 
-    // Build new args for child process
-    let mut child_args = args.map(|v| CString::new(v).unwrap()).collect::<Vec<CString>>();
-    child_args.insert(0, CString::new(child_cmd.as_str()).unwrap());
+        let bytes_written = write_cstr_chunk(pid, current_base, remaining).map_err(|err| {
+            format!(
+                "Unable to write to child process virtual memory at {:#x}: {}",
+                current_base, err,
+            )
+        })?;
 
-    eprintln!("CHILD: executing {} with argv {:?}...", child_cmd, child_args);
-    unistd::execvp(
-        &CString::new(child_cmd.as_str()).unwrap(),
-        child_args.as_slice(),
-    )
-        .expect(&format!("unable to execute {}", &child_cmd));
+        if bytes_written == 0 {
+            return Err(format!(
+                "Unable to write to child process virtual memory at {:#x}: no bytes written",
+                current_base,
+            ));
+        }
+
+        written += bytes_written;
+    }
+    Ok(())
+}
+
+/// Index (in the x86_64 syscall calling convention) of the register holding
+/// a given syscall argument: 0 => rdi, 1 => rsi, 2 => rdx, 3 => r10, 4 => r8,
+/// 5 => r9.
+fn syscall_arg_mut(regs: &mut libc::user_regs_struct, arg_index: u8) -> &mut u64 {
+    match arg_index {
+        0 => &mut regs.rdi,
+        1 => &mut regs.rsi,
+        2 => &mut regs.rdx,
+        3 => &mut regs.r10,
+        4 => &mut regs.r8,
+        5 => &mut regs.r9,
+        _ => panic!("Invalid syscall argument index {}", arg_index),
+    }
+}
+
+/// Redirect a path (or other string) syscall argument by writing `new_value`
+/// into a scratch region of the tracee's own stack, just below its current
+/// stack pointer, and pointing the argument register at it. Callers must
+/// still `ptrace::setregs` the patched `regs` before resuming the tracee.
+pub fn redirect_path_arg(pid: unistd::Pid, regs: &mut libc::user_regs_struct, arg_index: u8, new_value: &str) -> Result<(), String> {
+    let mut scratch = new_value.as_bytes().to_vec();
+    scratch.push(0);
+
+    // Stay well clear of the 128-byte x86_64 red zone below rsp.
+    let scratch_base = (regs.rsp as usize) - 512 - scratch.len();
+    put_child_buffer(pid, scratch_base, &scratch)?;
+
+    *syscall_arg_mut(regs, arg_index) = scratch_base as u64;
+    Ok(())
+}
+
+/// Neutralize the syscall about to run by replacing it with an invalid
+/// syscall number. Call from `handle_pre_syscall` (followed by
+/// `ptrace::setregs`) to deny a syscall outright; pair with `set_syscall_errno` in
+/// `handle_post_syscall` to report the denial back to the tracee.
+pub fn deny_syscall(regs: &mut libc::user_regs_struct) {
+    regs.orig_rax = std::u64::MAX;
+}
+
+/// Force the result of a denied syscall to look like it failed with `errno`,
+/// as with a normal syscall returning `-errno`. Intended to be paired with
+/// `deny_syscall` and applied to the post-syscall `regs` in
+/// `handle_post_syscall`.
+pub fn set_syscall_errno(regs: &mut libc::user_regs_struct, errno: nix::errno::Errno) {
+    regs.rax = (-(errno as i64)) as u64;
+}
+
+/// Convert an `OsStr` to an owned `CString` without requiring it to be valid
+/// UTF-8 (via `OsStrExt::as_bytes`), only requiring the absence of interior
+/// NUL bytes, same as any other C string.
+fn os_str_to_cstring(s: &OsStr) -> CString {
+    CString::new(s.as_bytes()).expect("argument, program name or env value contains a NUL byte")
+}
+
+/// Where to send a child's stdin/stdout/stderr.
+pub enum Redirect {
+    /// Leave the file descriptor as inherited from this process.
+    Inherit,
+    /// Replace the file descriptor with this one (closed in the child after
+    /// being dup2'd onto the target fd, unless it is itself one of the
+    /// standard fds 0, 1 or 2).
+    Fd(RawFd),
+}
+
+/// Builder for launching a traced child process with full control over its
+/// argv, environment and stdio, mirroring `std::process::Command` but built
+/// on raw `CString`/`OsString` so non-UTF-8 arguments don't panic.
+pub struct ChildCommand {
+    program: OsString,
+    args: Vec<OsString>,
+    env: HashMap<OsString, OsString>,
+    clear_env: bool,
+    stdin: Redirect,
+    stdout: Redirect,
+    stderr: Redirect,
+}
+
+impl ChildCommand {
+    pub fn new<S: AsRef<OsStr>>(program: S) -> ChildCommand {
+        ChildCommand {
+            program: program.as_ref().to_os_string(),
+            args: Vec::new(),
+            env: env::vars_os().collect(),
+            clear_env: false,
+            stdin: Redirect::Inherit,
+            stdout: Redirect::Inherit,
+            stderr: Redirect::Inherit,
+        }
+    }
+
+    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut ChildCommand {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut ChildCommand
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    /// Clear the child's inherited environment; only variables set via `env`
+    /// afterwards will be passed through.
+    pub fn env_clear(&mut self) -> &mut ChildCommand {
+        self.clear_env = true;
+        self.env.clear();
+        self
+    }
+
+    pub fn env<K: AsRef<OsStr>, V: AsRef<OsStr>>(&mut self, key: K, val: V) -> &mut ChildCommand {
+        self.env.insert(key.as_ref().to_os_string(), val.as_ref().to_os_string());
+        self
+    }
+
+    pub fn env_remove<K: AsRef<OsStr>>(&mut self, key: K) -> &mut ChildCommand {
+        self.env.remove(key.as_ref());
+        self
+    }
+
+    pub fn stdin(&mut self, redirect: Redirect) -> &mut ChildCommand {
+        self.stdin = redirect;
+        self
+    }
+
+    pub fn stdout(&mut self, redirect: Redirect) -> &mut ChildCommand {
+        self.stdout = redirect;
+        self
+    }
+
+    pub fn stderr(&mut self, redirect: Redirect) -> &mut ChildCommand {
+        self.stderr = redirect;
+        self
+    }
+
+    fn dup_redirect(fd: RawFd, redirect: &Redirect) {
+        if let Redirect::Fd(target) = redirect {
+            unistd::dup2(*target, fd).expect("CHILD: unable to redirect standard fd");
+        }
+    }
+
+    /// Enable tracing, apply the stdio redirections and `exec` into
+    /// `program`. Never returns on success; this must be called in the
+    /// forked child, not the tracer.
+    pub fn exec(&self) -> ! {
+        ptrace::traceme().expect("CHILD: could not enable tracing by parent (PTRACE_TRACEME failed)");
+
+        Self::dup_redirect(libc::STDIN_FILENO, &self.stdin);
+        Self::dup_redirect(libc::STDOUT_FILENO, &self.stdout);
+        Self::dup_redirect(libc::STDERR_FILENO, &self.stderr);
+
+        // Close each distinct source fd only after every dup2 above has
+        // run, in case two streams (e.g. stdout and stderr merged onto the
+        // same pipe) were redirected from the same fd.
+        let std_fds = [libc::STDIN_FILENO, libc::STDOUT_FILENO, libc::STDERR_FILENO];
+        let mut closed: Vec<RawFd> = Vec::new();
+        for redirect in &[&self.stdin, &self.stdout, &self.stderr] {
+            if let Redirect::Fd(target) = redirect {
+                if !std_fds.contains(target) && !closed.contains(target) {
+                    unistd::close(*target).expect("CHILD: unable to close redirected fd");
+                    closed.push(*target);
+                }
+            }
+        }
+
+        let program = os_str_to_cstring(&self.program);
+        let mut argv: Vec<CString> = Vec::with_capacity(self.args.len() + 1);
+        argv.push(program.clone());
+        argv.extend(self.args.iter().map(|a| os_str_to_cstring(a)));
+
+        let envp: Vec<CString> = self.env.iter()
+            .map(|(k, v)| {
+                let mut pair = k.as_bytes().to_vec();
+                pair.push(b'=');
+                pair.extend_from_slice(v.as_bytes());
+                CString::new(pair).expect("environment variable contains a NUL byte")
+            })
+            .collect();
+
+        eprintln!("CHILD: executing {:?} with argv {:?}...", self.program, argv);
+        unistd::execvpe(&program, argv.as_slice(), envp.as_slice())
+            .expect(&format!("unable to execute {:?}", &self.program));
+        unreachable!("execvpe only returns on error, which is handled above");
+    }
 }
 
 pub fn wait_child(pid: unistd::Pid) {
     wait::waitpid(pid, None).expect(&format!("Unable to wait for child PID {}", pid));
 }
 
-pub fn child_loop(child: unistd::Pid) {
-    let mut conf = syscalls::SyscallConfigMap::new();
-    let mut state = ProcessState::new();
-    loop {
-        // Await next child syscall
-        ptrace::syscall(child).expect("Unable to ask for next child syscall");
-        wait_child(child);
+/// Enable tracing of forks, vforks, clones and execs on `pid`, so that every
+/// descendant the tracee spawns is stopped before it can run and can be
+/// picked up by `child_loop`'s dispatch loop instead of escaping the firewall.
+fn set_trace_options(pid: unistd::Pid) {
+    let options = ptrace::Options::PTRACE_O_TRACEFORK
+        | ptrace::Options::PTRACE_O_TRACEVFORK
+        | ptrace::Options::PTRACE_O_TRACECLONE
+        | ptrace::Options::PTRACE_O_TRACEEXEC
+        // Tags syscall-stops with SIGTRAP|0x80 so they're reported as
+        // `WaitStatus::PtraceSyscall` instead of being indistinguishable
+        // from a genuine `WaitStatus::Stopped` signal-stop.
+        | ptrace::Options::PTRACE_O_TRACESYSGOOD;
+    ptrace::setoptions(pid, options).expect("Unable to set ptrace options on child");
+}
 
-        // Get syscall details
-        let mut regs = ptrace::getregs(child).expect("Unable to get syscall registers before servicing");
-        let syscall_id = regs.orig_rax;
+// PID of the root traced process, used by `forward_signal` to relay SIGINT
+// and SIGTERM from this (the tracer's) process down to the tracee. Plain
+// `kill(2)` rather than anything ptrace-specific, since it just needs to
+// reach the traced process the same way it would reach any other.
+static TRACED_ROOT_PID: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(0);
 
-        let handler_res = syscalls::handle_pre_syscall(&mut conf, &mut state, child, syscall_id, &mut regs);
+extern "C" fn forward_signal(raw_signal: libc::c_int) {
+    let pid = TRACED_ROOT_PID.load(std::sync::atomic::Ordering::SeqCst);
+    if pid != 0 {
+        unsafe { libc::kill(pid, raw_signal); }
+    }
+}
 
-        // Execute this child syscall
-        ptrace::syscall(child).expect("Unable to execute current child syscall");
-        wait_child(child);
+/// Forward SIGINT/SIGTERM delivered to syswall itself down to the traced
+/// root process, so e.g. Ctrl-C interrupts the sandboxed program instead of
+/// only killing the tracer and leaving the tracee running (or stopped).
+fn install_signal_forwarding(child: unistd::Pid) {
+    TRACED_ROOT_PID.store(child.as_raw(), std::sync::atomic::Ordering::SeqCst);
+    let handler = signal::SigHandler::Handler(forward_signal);
+    unsafe {
+        signal::signal(signal::Signal::SIGINT, handler).expect("Unable to install SIGINT handler");
+        signal::signal(signal::Signal::SIGTERM, handler).expect("Unable to install SIGTERM handler");
+    }
+}
 
-        // Get syscall result
-        match ptrace::getregs(child) {
-            Ok(ref mut regs) => {
-                syscalls::handle_post_syscall(handler_res, &mut state, child, syscall_id, regs);
-            },
+/// Called on a fork/vfork/clone ptrace-event stop of `parent`:
+/// fetch the new child's PID via PTRACE_GETEVENTMSG, seed its `ProcessState`
+/// and start tracing it alongside every other process we're watching.
+fn handle_new_child(states: &mut HashMap<unistd::Pid, ProcessState>, parent: unistd::Pid) {
+    let new_pid = ptrace::getevent(parent).expect("Unable to read new child PID from ptrace event");
+    let child_pid = unistd::Pid::from_raw(new_pid as i32);
+    states.insert(child_pid, ProcessState::new());
+    set_trace_options(child_pid);
+}
+
+/// Drive every process in the traced tree from a single dispatch loop.
+///
+/// `waitpid(-1, ...)` is used instead of waiting on a single PID so that the
+/// loop picks up whichever tracee (root process or any fork/vfork/clone
+/// descendant) stops next. The loop exits once every tracked process has
+/// exited and `states` is empty.
+pub fn child_loop(child: unistd::Pid) {
+    let mut conf = syscalls::SyscallConfigMap::new();
+    let mut states: HashMap<unistd::Pid, ProcessState> = HashMap::new();
+    states.insert(child, ProcessState::new());
+    set_trace_options(child);
+    install_signal_forwarding(child);
+
+    // Tracks, per-PID, whether the next stop we see for it is the pre-syscall
+    // half (true) or the post-syscall half (false) of the syscall-stop pair.
+    let mut awaiting_syscall_entry: HashMap<unistd::Pid, bool> = HashMap::new();
+    let mut pending_syscall_id: HashMap<unistd::Pid, u64> = HashMap::new();
+    let mut pending_handler_res: HashMap<unistd::Pid, _> = HashMap::new();
+
+    while !states.is_empty() {
+        let status = match wait::waitpid(unistd::Pid::from_raw(-1), None) {
+            Ok(status) => status,
             Err(err) => {
-                if err.as_errno() == Some(nix::errno::Errno::ESRCH) {
-                    eprintln!("\nChild process terminated");
+                if err.as_errno() == Some(nix::errno::Errno::ECHILD) {
                     break;
                 }
-                eprintln!("Unable to get syscall registers after servicing");
+                eprintln!("Unable to wait for any traced child");
+                continue;
+            },
+        };
+
+        match status {
+            WaitStatus::Exited(pid, _) | WaitStatus::Signaled(pid, _, _) => {
+                eprintln!("\nTraced process {} terminated", pid);
+                states.remove(&pid);
+                awaiting_syscall_entry.remove(&pid);
+                pending_syscall_id.remove(&pid);
+                pending_handler_res.remove(&pid);
+            },
+            WaitStatus::PtraceEvent(pid, _sig, event) => {
+                if event == Event::PTRACE_EVENT_FORK as i32
+                    || event == Event::PTRACE_EVENT_VFORK as i32
+                    || event == Event::PTRACE_EVENT_CLONE as i32
+                {
+                    handle_new_child(&mut states, pid);
+                } else if event == Event::PTRACE_EVENT_EXEC as i32 {
+                    // The tracee has replaced its image; its open file
+                    // table no longer applies, so start it fresh. The exec
+                    // syscall's own exit-stop is consumed by this event
+                    // (PTRACE_O_TRACEEXEC), so any pre-syscall state we were
+                    // holding for it is now stale and must be dropped too,
+                    // or every later syscall stop for this PID is off by one.
+                    if let Some(state) = states.get_mut(&pid) {
+                        *state = ProcessState::new();
+                    }
+                    awaiting_syscall_entry.remove(&pid);
+                    pending_syscall_id.remove(&pid);
+                    pending_handler_res.remove(&pid);
+                }
+                ptrace::syscall(pid, None).expect("Unable to resume traced process after ptrace event");
+            },
+            WaitStatus::Stopped(pid, sig) => {
+                // A genuine signal-stop, not a syscall boundary (TRACESYSGOOD
+                // is in effect, so syscall-stops arrive as `PtraceSyscall`
+                // instead). Re-inject the signal so the tracee's own
+                // handlers run rather than swallowing it.
+                //
+                // Stop signals are the exception: a newly attached
+                // fork/vfork/clone child (see `handle_new_child`, which never
+                // itself resumes the child) is first observed right here,
+                // carrying its initial-attach SIGSTOP, and re-injecting a
+                // stop signal through PTRACE_SYSCALL's signal argument would
+                // put the tracee into a group-stop instead of letting it
+                // run. Resume past these with no signal instead.
+                let resume_sig = match sig {
+                    signal::Signal::SIGSTOP
+                    | signal::Signal::SIGTSTP
+                    | signal::Signal::SIGTTIN
+                    | signal::Signal::SIGTTOU => None,
+                    _ => Some(sig),
+                };
+                ptrace::syscall(pid, resume_sig).expect("Unable to resume traced process past signal stop");
+            },
+            WaitStatus::PtraceSyscall(pid) => {
+                let entering = *awaiting_syscall_entry.entry(pid).or_insert(true);
+                if entering {
+                    let mut regs = ptrace::getregs(pid).expect("Unable to get syscall registers before servicing");
+                    let syscall_id = regs.orig_rax;
+                    let state = states.get_mut(&pid).expect("Syscall stop for untracked PID");
+
+                    let handler_res = syscalls::handle_pre_syscall(&mut conf, state, pid, syscall_id, &mut regs);
+
+                    pending_syscall_id.insert(pid, syscall_id);
+                    pending_handler_res.insert(pid, handler_res);
+                    awaiting_syscall_entry.insert(pid, false);
+                } else {
+                    let syscall_id = pending_syscall_id.remove(&pid).expect("Missing pending syscall id");
+                    let handler_res = pending_handler_res.remove(&pid).expect("Missing pending handler result");
+                    let state = states.get_mut(&pid).expect("Syscall stop for untracked PID");
+
+                    match ptrace::getregs(pid) {
+                        Ok(ref mut regs) => {
+                            syscalls::handle_post_syscall(handler_res, state, pid, syscall_id, regs);
+                        },
+                        Err(err) => {
+                            if err.as_errno() != Some(nix::errno::Errno::ESRCH) {
+                                eprintln!("Unable to get syscall registers after servicing");
+                            }
+                        },
+                    };
+                    awaiting_syscall_entry.insert(pid, true);
+                }
+                ptrace::syscall(pid, None).expect("Unable to ask for next syscall stop");
             },
+            _ => {},
         };
     }
 }
\ No newline at end of file